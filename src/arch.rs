@@ -9,6 +9,8 @@ pub enum Arch {
     Ppc64le,
     Ppc64,
     S390x,
+    Riscv64,
+    Loongarch64,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -51,6 +53,12 @@ impl DownloadPath for LibC {
     }
 }
 
+impl DownloadPath for Arch {
+    fn download_path(&self) -> String {
+        self.to_string()
+    }
+}
+
 impl Default for Arch {
     fn default() -> Arch {
         match crate::system_info::platform_arch().parse() {
@@ -62,13 +70,145 @@ impl Default for Arch {
 
 impl Default for LibC {
     fn default() -> LibC {
-        match os_type::current_platform().os_type {
-            os_type::OSType::Alpine => LibC::Musl,
-            _ => LibC::Glibc,
+        match detect_libc() {
+            Some(libc) => libc,
+            None => match os_type::current_platform().os_type {
+                os_type::OSType::Alpine => LibC::Musl,
+                _ => LibC::Glibc,
+            },
         }
     }
 }
 
+/// The outcome of probing the host for its actual libc. `LibC::default()`
+/// and the diagnostics report work from `detect_libc()`'s `Option<LibC>`
+/// directly and do not use this type.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LibCDetection {
+    /// The running process's own dynamic loader was identified as musl.
+    Musl,
+    /// The running process's own dynamic loader was identified as glibc.
+    Glibc,
+    /// Neither probe could determine the libc; the Alpine-only heuristic
+    /// was used instead.
+    Unknown,
+}
+
+impl std::fmt::Display for LibCDetection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let detection_str = match self {
+            LibCDetection::Musl => "musl (detected)",
+            LibCDetection::Glibc => "glibc (detected)",
+            LibCDetection::Unknown => "unknown (fell back to Alpine heuristic)",
+        };
+        write!(f, "{}", detection_str)
+    }
+}
+
+/// Actively probes the host's libc rather than relying solely on the distro
+/// name, so musl-based distros other than Alpine (e.g. Void) and
+/// glibc-in-a-musl-container edge cases are classified correctly.
+///
+/// First reads the ELF interpreter of the running process from
+/// `/proc/self/exe`, looking for the `musl` vs `ld-linux`/`libc.so` naming
+/// convention used by each dynamic loader. If that fails (e.g. non-Linux,
+/// statically linked, or `/proc` unavailable), falls back to running
+/// `ldd --version` and checking its banner for the `musl libc` signature.
+#[cfg(unix)]
+pub fn detect_libc() -> Option<LibC> {
+    if let Some(libc) = detect_libc_from_interpreter() {
+        return Some(libc);
+    }
+
+    detect_libc_from_ldd()
+}
+
+#[cfg(not(unix))]
+pub fn detect_libc() -> Option<LibC> {
+    None
+}
+
+#[cfg(unix)]
+fn detect_libc_from_interpreter() -> Option<LibC> {
+    let contents = std::fs::read("/proc/self/exe").ok()?;
+    let interp = read_elf_interpreter(&contents)?;
+
+    if interp.contains("musl") {
+        Some(LibC::Musl)
+    } else if interp.contains("ld-linux") || interp.contains("libc.so") {
+        Some(LibC::Glibc)
+    } else {
+        None
+    }
+}
+
+/// Extracts the `PT_INTERP` string (the path to the dynamic loader) from a
+/// little-endian ELF binary's program headers, without pulling in a full
+/// ELF-parsing dependency for a single lookup.
+#[cfg(unix)]
+fn read_elf_interpreter(elf: &[u8]) -> Option<String> {
+    const PT_INTERP: u32 = 3;
+    let is_64bit = *elf.get(4)? == 2;
+
+    let (phoff, phentsize, phnum) = if is_64bit {
+        (
+            u64::from_le_bytes(elf.get(32..40)?.try_into().ok()?) as usize,
+            u16::from_le_bytes(elf.get(54..56)?.try_into().ok()?) as usize,
+            u16::from_le_bytes(elf.get(56..58)?.try_into().ok()?) as usize,
+        )
+    } else {
+        (
+            u32::from_le_bytes(elf.get(28..32)?.try_into().ok()?) as usize,
+            u16::from_le_bytes(elf.get(42..44)?.try_into().ok()?) as usize,
+            u16::from_le_bytes(elf.get(44..46)?.try_into().ok()?) as usize,
+        )
+    };
+
+    for i in 0..phnum {
+        let header = elf.get(phoff + i * phentsize..)?;
+        let p_type = u32::from_le_bytes(header.get(0..4)?.try_into().ok()?);
+        if p_type != PT_INTERP {
+            continue;
+        }
+
+        let (offset, filesz) = if is_64bit {
+            (
+                u64::from_le_bytes(header.get(8..16)?.try_into().ok()?) as usize,
+                u64::from_le_bytes(header.get(32..40)?.try_into().ok()?) as usize,
+            )
+        } else {
+            (
+                u32::from_le_bytes(header.get(4..8)?.try_into().ok()?) as usize,
+                u32::from_le_bytes(header.get(16..20)?.try_into().ok()?) as usize,
+            )
+        };
+
+        let path = elf.get(offset..offset + filesz)?;
+        return Some(String::from_utf8_lossy(path).trim_end_matches('\0').to_string());
+    }
+
+    None
+}
+
+#[cfg(unix)]
+fn detect_libc_from_ldd() -> Option<LibC> {
+    let output = std::process::Command::new("ldd").arg("--version").output().ok()?;
+    let banner = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+    .to_lowercase();
+
+    if banner.contains("musl") {
+        Some(LibC::Musl)
+    } else if banner.contains("gnu libc") || banner.contains("glibc") {
+        Some(LibC::Glibc)
+    } else {
+        None
+    }
+}
+
 impl std::str::FromStr for Arch {
     type Err = ArchError;
     fn from_str(s: &str) -> Result<Arch, Self::Err> {
@@ -80,6 +220,8 @@ impl std::str::FromStr for Arch {
             "ppc64le" => Ok(Arch::Ppc64le),
             "ppc64" => Ok(Arch::Ppc64),
             "s390x" => Ok(Arch::S390x),
+            "riscv64" => Ok(Arch::Riscv64),
+            "loongarch64" => Ok(Arch::Loongarch64),
             unknown => Err(ArchError::new(&format!("Unknown Arch: {}", unknown))),
         }
     }
@@ -107,6 +249,8 @@ impl std::fmt::Display for Arch {
             Arch::Ppc64le => String::from("ppc64le"),
             Arch::Ppc64 => String::from("ppc64"),
             Arch::S390x => String::from("s390x"),
+            Arch::Riscv64 => String::from("riscv64"),
+            Arch::Loongarch64 => String::from("loongarch64"),
         };
 
         write!(f, "{}", arch_str)
@@ -172,4 +316,73 @@ impl std::error::Error for LibCError {
     fn description(&self) -> &str {
         &self.details
     }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Builds a minimal little-endian ELF64 image with a single `PT_INTERP`
+    /// program header pointing at `interp`, just enough for
+    /// `read_elf_interpreter` to parse.
+    fn build_elf64_with_interp(interp: &str) -> Vec<u8> {
+        const EHDR_SIZE: u64 = 64;
+        const PHDR_SIZE: u64 = 56;
+
+        let interp_bytes = interp.as_bytes();
+        let interp_offset = EHDR_SIZE + PHDR_SIZE;
+        let filesz = (interp_bytes.len() + 1) as u64;
+
+        let mut buf = vec![0u8; (interp_offset + filesz) as usize];
+        buf[4] = 2; // EI_CLASS = ELFCLASS64
+        buf[32..40].copy_from_slice(&EHDR_SIZE.to_le_bytes()); // e_phoff
+        buf[54..56].copy_from_slice(&(PHDR_SIZE as u16).to_le_bytes()); // e_phentsize
+        buf[56..58].copy_from_slice(&1u16.to_le_bytes()); // e_phnum
+
+        let phdr = EHDR_SIZE as usize;
+        buf[phdr..phdr + 4].copy_from_slice(&3u32.to_le_bytes()); // p_type = PT_INTERP
+        buf[phdr + 8..phdr + 16].copy_from_slice(&interp_offset.to_le_bytes()); // p_offset
+        buf[phdr + 32..phdr + 40].copy_from_slice(&filesz.to_le_bytes()); // p_filesz
+
+        let interp_start = interp_offset as usize;
+        buf[interp_start..interp_start + interp_bytes.len()].copy_from_slice(interp_bytes);
+
+        buf
+    }
+
+    #[test]
+    fn read_elf_interpreter_finds_musl_loader() {
+        let elf = build_elf64_with_interp("/lib/ld-musl-x86_64.so.1");
+        assert_eq!(
+            read_elf_interpreter(&elf),
+            Some("/lib/ld-musl-x86_64.so.1".to_string())
+        );
+    }
+
+    #[test]
+    fn read_elf_interpreter_finds_glibc_loader() {
+        let elf = build_elf64_with_interp("/lib64/ld-linux-x86-64.so.2");
+        assert_eq!(
+            read_elf_interpreter(&elf),
+            Some("/lib64/ld-linux-x86-64.so.2".to_string())
+        );
+    }
+
+    #[test]
+    fn read_elf_interpreter_is_none_when_there_is_no_phdr() {
+        let mut buf = vec![0u8; 64];
+        buf[4] = 2;
+        assert_eq!(read_elf_interpreter(&buf), None);
+    }
+
+    #[test]
+    fn read_elf_interpreter_returns_paths_carrying_the_loader_signature() {
+        let musl = build_elf64_with_interp("/lib/ld-musl-x86_64.so.1");
+        let interp = read_elf_interpreter(&musl).expect("should find PT_INTERP");
+        assert!(interp.contains("musl"));
+
+        let glibc = build_elf64_with_interp("/lib64/ld-linux-x86-64.so.2");
+        let interp = read_elf_interpreter(&glibc).expect("should find PT_INTERP");
+        assert!(interp.contains("ld-linux"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Extracts a gzipped tarball into `dest`, dropping each entry's leading
+/// path component. Node (and local archive) tarballs wrap their contents
+/// in a single top-level `node-{version}-{platform}-{arch}/` directory, so
+/// a plain `tar::Archive::unpack` would otherwise land everything one
+/// directory too deep (`dest/node-.../bin/node` instead of
+/// `dest/bin/node`). Shared by every call site that unpacks a prebuilt
+/// Node tarball: the prebuilt install, the local archive install, and the
+/// cross-target install.
+pub fn extract_stripping_top_level_dir<R: Read>(reader: R, dest: &Path) -> std::io::Result<()> {
+    let tar = flate2::read::GzDecoder::new(reader);
+    let mut archive = tar::Archive::new(tar);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let relative: PathBuf = path.components().skip(1).collect();
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let target = dest.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        entry.unpack(target)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gzipped_tarball_with_top_level_dir() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let contents = b"#!/usr/bin/env node\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "node-v18.16.0-linux-x64/bin/node", &contents[..])
+            .expect("can append a tar entry");
+        let tar_bytes = builder.into_inner().expect("can finish the tar archive");
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &tar_bytes).expect("can gzip the tar archive");
+        encoder.finish().expect("can finish gzip encoding")
+    }
+
+    #[test]
+    fn strips_the_top_level_directory() {
+        let dir = tempfile::tempdir().expect("can create a tempdir");
+        let archive = gzipped_tarball_with_top_level_dir();
+
+        extract_stripping_top_level_dir(&archive[..], dir.path()).expect("should unpack");
+
+        assert!(dir.path().join("bin/node").is_file());
+        assert!(!dir.path().join("node-v18.16.0-linux-x64").exists());
+    }
+}
@@ -0,0 +1,251 @@
+use std::process::Command;
+
+use crate::arch::{Arch, InstallDir};
+use crate::config::FnmConfig;
+use crate::installer::InstallTarget;
+use crate::version::Version;
+
+/// Controls whether fnm is allowed to compile Node from source when no
+/// prebuilt tarball exists for the requested `Arch`/`LibC` combination.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BuildFromSourceMode {
+    /// Only build from source if the prebuilt download isn't available.
+    Auto,
+    /// Always compile from source, even if a prebuilt tarball exists.
+    Always,
+    /// Never build from source; fail instead of falling back.
+    Never,
+}
+
+impl Default for BuildFromSourceMode {
+    fn default() -> Self {
+        BuildFromSourceMode::Auto
+    }
+}
+
+impl std::str::FromStr for BuildFromSourceMode {
+    type Err = BuildFromSourceModeError;
+    fn from_str(s: &str) -> Result<BuildFromSourceMode, Self::Err> {
+        match s {
+            "auto" => Ok(BuildFromSourceMode::Auto),
+            "always" => Ok(BuildFromSourceMode::Always),
+            "never" => Ok(BuildFromSourceMode::Never),
+            unknown => Err(BuildFromSourceModeError::new(&format!(
+                "Unknown FNM_BUILD_FROM_SOURCE value: {}",
+                unknown
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for BuildFromSourceMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode_str = match self {
+            BuildFromSourceMode::Auto => "auto",
+            BuildFromSourceMode::Always => "always",
+            BuildFromSourceMode::Never => "never",
+        };
+        write!(f, "{}", mode_str)
+    }
+}
+
+#[derive(Debug)]
+pub struct BuildFromSourceModeError {
+    details: String,
+}
+
+impl BuildFromSourceModeError {
+    fn new(msg: &str) -> BuildFromSourceModeError {
+        BuildFromSourceModeError {
+            details: msg.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for BuildFromSourceModeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for BuildFromSourceModeError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+#[derive(Debug)]
+pub struct SourceBuildError {
+    details: String,
+}
+
+impl SourceBuildError {
+    fn new(msg: impl Into<String>) -> SourceBuildError {
+        SourceBuildError {
+            details: msg.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for SourceBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for SourceBuildError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+/// A C/C++ toolchain detected on the host, either from `CC`/`CXX` or a
+/// `cc`/`c++` found on `PATH`.
+struct Toolchain {
+    cc: String,
+    cxx: String,
+}
+
+fn detect_toolchain() -> Result<Toolchain, SourceBuildError> {
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let cxx = std::env::var("CXX").unwrap_or_else(|_| "c++".to_string());
+
+    let cc_found = Command::new(&cc).arg("--version").output().is_ok();
+    if !cc_found {
+        return Err(SourceBuildError::new(format!(
+            "No C/C++ toolchain found (looked for `{}`). \
+             Set the CC and CXX environment variables to point at a compiler, \
+             or set FNM_BUILD_FROM_SOURCE=never to disable building from source.",
+            cc
+        )));
+    }
+
+    Ok(Toolchain { cc, cxx })
+}
+
+/// Source tarball URL for a given Node version, using the same mirror as
+/// prebuilt downloads.
+fn source_tarball_url(config: &FnmConfig, version: &Version) -> reqwest::Url {
+    config
+        .node_dist_mirror
+        .join(&format!("{}/node-{}.tar.gz", version, version))
+        .expect("Can't build source tarball URL")
+}
+
+/// Builds and installs Node from source for `arch`, placing the result in
+/// the same directory layout a prebuilt install would use.
+pub fn build_from_source(
+    config: &FnmConfig,
+    version: &Version,
+    arch: &Arch,
+) -> Result<(), SourceBuildError> {
+    let toolchain = detect_toolchain()?;
+
+    let install_dir = InstallTarget { config, version }.path();
+
+    let source_url = source_tarball_url(config, version);
+    let response = reqwest::blocking::get(source_url.clone())
+        .map_err(|e| SourceBuildError::new(format!("Can't download {}: {}", source_url, e)))?;
+    if !response.status().is_success() {
+        return Err(SourceBuildError::new(format!(
+            "Source tarball not found at {} (status {})",
+            source_url,
+            response.status()
+        )));
+    }
+
+    let build_dir = tempfile::tempdir()
+        .map_err(|e| SourceBuildError::new(format!("Can't create a build directory: {}", e)))?;
+    let tar = flate2::read::GzDecoder::new(response);
+    let mut archive = tar::Archive::new(tar);
+    archive
+        .unpack(build_dir.path())
+        .map_err(|e| SourceBuildError::new(format!("Can't extract source tarball: {}", e)))?;
+
+    let source_dir = build_dir.path().join(format!("node-{}", version));
+
+    run_step(
+        &source_dir,
+        "./configure",
+        &[
+            "--prefix",
+            install_dir
+                .to_str()
+                .expect("install_dir should be valid UTF-8"),
+            "--dest-cpu",
+            &arch.to_string(),
+        ],
+        &toolchain,
+    )?;
+    run_step(&source_dir, "make", &[], &toolchain)?;
+    run_step(&source_dir, "make", &["install"], &toolchain)?;
+
+    Ok(())
+}
+
+fn run_step(
+    cwd: &std::path::Path,
+    program: &str,
+    args: &[&str],
+    toolchain: &Toolchain,
+) -> Result<(), SourceBuildError> {
+    let status = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .env("CC", &toolchain.cc)
+        .env("CXX", &toolchain.cxx)
+        .status()
+        .map_err(|e| SourceBuildError::new(format!("Can't run `{}`: {}", program, e)))?;
+
+    if !status.success() {
+        return Err(SourceBuildError::new(format!(
+            "`{}` exited with {}",
+            program, status
+        )));
+    }
+
+    Ok(())
+}
+
+/// Whether a build-from-source fallback should be attempted after a
+/// prebuilt download has failed, given the configured `mode`.
+pub fn should_fall_back(mode: &BuildFromSourceMode) -> bool {
+    matches!(mode, BuildFromSourceMode::Auto | BuildFromSourceMode::Always)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_when_auto() {
+        assert!(should_fall_back(&BuildFromSourceMode::Auto));
+    }
+
+    #[test]
+    fn falls_back_when_always() {
+        assert!(should_fall_back(&BuildFromSourceMode::Always));
+    }
+
+    #[test]
+    fn does_not_fall_back_when_never() {
+        assert!(!should_fall_back(&BuildFromSourceMode::Never));
+    }
+
+    #[test]
+    fn mode_round_trips_through_display_and_from_str() {
+        for mode in [
+            BuildFromSourceMode::Auto,
+            BuildFromSourceMode::Always,
+            BuildFromSourceMode::Never,
+        ] {
+            let parsed: BuildFromSourceMode = mode.to_string().parse().expect("should re-parse");
+            assert_eq!(parsed, mode);
+        }
+    }
+
+    #[test]
+    fn rejects_an_unknown_mode() {
+        assert!("bogus".parse::<BuildFromSourceMode>().is_err());
+    }
+}
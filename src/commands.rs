@@ -0,0 +1,61 @@
+use structopt::StructOpt;
+
+use crate::config::FnmConfig;
+use crate::cross_install::{install_cross_target, CrossInstallOptions};
+use crate::diagnostics::libc_report;
+use crate::file_config::{self, ConfigSource};
+use crate::version::Version;
+
+/// fnm's subcommands that aren't version-management (those stay as
+/// top-level flags on `FnmConfig` for backwards compatibility); each one
+/// is dispatched from `SubCommand::call`.
+#[derive(StructOpt, Debug)]
+pub enum SubCommand {
+    /// Show the effective configuration and where each value came from.
+    Config(ConfigCommand),
+    /// Print diagnostic info about how fnm detected the host environment.
+    Diagnose,
+    /// Install a Node build for a target that may differ from the host.
+    #[structopt(name = "install")]
+    CrossInstall(CrossInstallCommand),
+}
+
+#[derive(StructOpt, Debug)]
+pub struct CrossInstallCommand {
+    pub version: Version,
+    #[structopt(flatten)]
+    pub target: CrossInstallOptions,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct ConfigCommand {
+    /// Print the effective value of each layered config key next to the
+    /// layer (cli/env/project file/user file/default) that set it.
+    #[structopt(long)]
+    pub show: bool,
+}
+
+impl SubCommand {
+    pub fn call(
+        self,
+        config: FnmConfig,
+        sources: &[(&'static str, ConfigSource)],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            SubCommand::Config(cmd) => {
+                if cmd.show {
+                    print!("{}", file_config::format_effective_config(&config, sources));
+                }
+                Ok(())
+            }
+            SubCommand::Diagnose => {
+                println!("{}", libc_report());
+                Ok(())
+            }
+            SubCommand::CrossInstall(cmd) => {
+                install_cross_target(&config, &cmd.version, &cmd.target)?;
+                Ok(())
+            }
+        }
+    }
+}
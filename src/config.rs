@@ -5,6 +5,9 @@ use structopt::StructOpt;
 
 use crate::arch;
 use crate::arch::LibC;
+use crate::build_from_source::BuildFromSourceMode;
+use crate::commands::SubCommand;
+use crate::file_config::{self, ConfigSource, FileConfigError};
 use crate::log_level::LogLevel;
 use crate::path_ext::PathExt;
 
@@ -73,6 +76,34 @@ pub struct FnmConfig {
     hide_env_values = true
     )]
     pub libc: arch::LibC,
+
+    /// Whether to compile Node from source when no prebuilt binary matches
+    /// the Arch/LibC combination. `auto` falls back to a source build only
+    /// when the prebuilt download isn't available, `always` skips the
+    /// prebuilt download entirely, and `never` fails instead of building.
+    #[structopt(
+    long,
+    env = "FNM_BUILD_FROM_SOURCE",
+    default_value,
+    global = true,
+    hide_env_values = true
+    )]
+    pub build_from_source: BuildFromSourceMode,
+
+    /// Install from a pre-downloaded tarball instead of reaching out to
+    /// `node_dist_mirror`. Accepts either a `file://` path to a single
+    /// tarball or a directory of cached tarballs named like the official
+    /// release artifacts, e.g. `node-v18.16.0-linux-x64.tar.gz`.
+    #[structopt(
+    long = "node-archive",
+    env = "FNM_NODE_ARCHIVE",
+    global = true,
+    hide_env_values = true
+    )]
+    pub node_archive: Option<String>,
+
+    #[structopt(subcommand)]
+    pub subcmd: Option<SubCommand>,
 }
 
 lazy_static! {
@@ -95,11 +126,29 @@ impl Default for FnmConfig {
             log_level: LogLevel::Info,
             arch: Default::default(),
             libc: Default::default(),
+            build_from_source: Default::default(),
+            node_archive: None,
+            subcmd: None,
         }
     }
 }
 
 impl FnmConfig {
+    /// Parses CLI args into `FnmConfig`, having first layered the project
+    /// `fnm.toml`, then the user `config.toml`, into the environment
+    /// wherever a CLI flag or an explicit env var doesn't already govern a
+    /// key. Returns the sources
+    /// that ended up governing each layered key, for `fnm config --show`.
+    ///
+    /// Use this instead of `FnmConfig::from_args()` directly: the file
+    /// layers have to be applied before structopt parses args for them to
+    /// take effect.
+    pub fn load_merged() -> Result<(Self, Vec<(&'static str, ConfigSource)>), FileConfigError> {
+        let (project, user) = file_config::load_layers()?;
+        let sources = file_config::apply_env_fallbacks(&project, &user);
+        Ok((Self::from_args(), sources))
+    }
+
     pub fn multishell_path(&self) -> Option<&std::path::Path> {
         match &self.multishell_path {
             None => None,
@@ -111,6 +160,14 @@ impl FnmConfig {
         &self.log_level
     }
 
+    /// The resolved `FNM_NODE_ARCHIVE` / `--node-archive` location, with a
+    /// `file://` scheme stripped if present.
+    pub fn node_archive(&self) -> Option<std::path::PathBuf> {
+        self.node_archive
+            .as_deref()
+            .map(crate::local_archive::parse_archive_location)
+    }
+
     pub fn base_dir_with_default(&self) -> std::path::PathBuf {
         self.base_dir
             .clone()
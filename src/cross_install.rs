@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+
+use structopt::StructOpt;
+
+use crate::arch::{Arch, LibC};
+use crate::archive_extract::extract_stripping_top_level_dir;
+use crate::config::FnmConfig;
+use crate::installer::prebuilt_archive_name;
+use crate::version::Version;
+
+/// `fnm install --target-arch=<arch> --target-libc=<libc> --prefix=<dir>`:
+/// installs a Node build for a target that may differ from the host,
+/// producing a self-contained, relocatable directory (e.g. to populate a
+/// container image or deploy artifact) instead of a binary the host can run.
+#[derive(StructOpt, Debug)]
+pub struct CrossInstallOptions {
+    /// The architecture to install for. Unlike a normal install, this is
+    /// used as-is: `get_safe_arch`'s host-based rewriting is skipped, since
+    /// there's no host binary to work around.
+    #[structopt(long = "target-arch")]
+    pub target_arch: Arch,
+
+    /// The libc to install for.
+    #[structopt(long = "target-libc")]
+    pub target_libc: LibC,
+
+    /// Directory to install the relocatable Node tree into.
+    #[structopt(long, parse(from_os_str))]
+    pub prefix: PathBuf,
+}
+
+/// Describes a cross-installed Node tree so later tooling (e.g. a container
+/// build step) can consume it without re-deriving the target from the
+/// binary layout.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct TargetManifest {
+    pub version: String,
+    pub arch: String,
+    pub libc: String,
+}
+
+impl TargetManifest {
+    const FILE_NAME: &'static str = "fnm-target-manifest.json";
+
+    pub fn write(&self, prefix: &std::path::Path) -> Result<(), CrossInstallError> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| CrossInstallError::new(format!("Can't serialize manifest: {}", e)))?;
+        std::fs::write(prefix.join(Self::FILE_NAME), contents)
+            .map_err(|e| CrossInstallError::new(format!("Can't write manifest: {}", e)))
+    }
+}
+
+#[derive(Debug)]
+pub struct CrossInstallError {
+    details: String,
+}
+
+impl CrossInstallError {
+    fn new(msg: impl Into<String>) -> CrossInstallError {
+        CrossInstallError {
+            details: msg.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for CrossInstallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for CrossInstallError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+/// Downloads and extracts `version` for `options.target_arch`/`target_libc`
+/// into `options.prefix`, skipping the host-based `get_safe_arch` rewriting
+/// that a same-host install would apply, and never executing the
+/// downloaded binary (e.g. to probe its version) since it may not be able
+/// to run on the host.
+pub fn install_cross_target(
+    config: &FnmConfig,
+    version: &Version,
+    options: &CrossInstallOptions,
+) -> Result<(), CrossInstallError> {
+    std::fs::create_dir_all(&options.prefix)
+        .map_err(|e| CrossInstallError::new(format!("Can't create {}: {}", options.prefix.display(), e)))?;
+
+    let download_url = config
+        .node_dist_mirror
+        .join(&format!(
+            "{}/{}",
+            version,
+            prebuilt_archive_name(version, &options.target_arch, &options.target_libc)
+        ))
+        .map_err(|e| CrossInstallError::new(format!("Can't build download URL: {}", e)))?;
+
+    let response = reqwest::blocking::get(download_url.clone())
+        .map_err(|e| CrossInstallError::new(format!("Can't download {}: {}", download_url, e)))?;
+    if !response.status().is_success() {
+        return Err(CrossInstallError::new(format!(
+            "No prebuilt tarball at {} (status {})",
+            download_url,
+            response.status()
+        )));
+    }
+
+    extract_stripping_top_level_dir(response, &options.prefix)
+        .map_err(|e| CrossInstallError::new(format!("Can't extract archive: {}", e)))?;
+
+    TargetManifest {
+        version: version.to_string(),
+        arch: options.target_arch.to_string(),
+        libc: options.target_libc.to_string(),
+    }
+    .write(&options.prefix)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_manifest_round_trips_through_json() {
+        let manifest = TargetManifest {
+            version: "v18.16.0".to_string(),
+            arch: "x64".to_string(),
+            libc: "musl".to_string(),
+        };
+
+        let json = serde_json::to_string(&manifest).expect("should serialize");
+        let parsed: TargetManifest = serde_json::from_str(&json).expect("should deserialize");
+
+        assert_eq!(parsed.version, manifest.version);
+        assert_eq!(parsed.arch, manifest.arch);
+        assert_eq!(parsed.libc, manifest.libc);
+    }
+
+    #[test]
+    fn target_manifest_write_creates_the_expected_file() {
+        let dir = tempfile::tempdir().expect("can create a tempdir");
+        let manifest = TargetManifest {
+            version: "v18.16.0".to_string(),
+            arch: "x64".to_string(),
+            libc: "".to_string(),
+        };
+
+        manifest.write(dir.path()).expect("should write the manifest");
+
+        let contents = std::fs::read_to_string(dir.path().join(TargetManifest::FILE_NAME))
+            .expect("manifest file should exist");
+        assert!(contents.contains("v18.16.0"));
+    }
+}
@@ -0,0 +1,15 @@
+use crate::arch::{detect_libc, LibC};
+
+/// Human-readable report of how fnm decided which libc/mirror to use,
+/// surfaced by the `fnm` diagnostic command so users can see why a given
+/// libc/mirror was chosen instead of having to guess at the heuristic.
+pub fn libc_report() -> String {
+    match detect_libc() {
+        Some(LibC::Musl) => "libc: musl (detected from the host's dynamic loader)".to_string(),
+        Some(LibC::Glibc) => "libc: glibc (detected from the host's dynamic loader)".to_string(),
+        None => {
+            "libc: could not be actively detected; falling back to the Alpine-only heuristic"
+                .to_string()
+        }
+    }
+}
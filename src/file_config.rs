@@ -0,0 +1,309 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// The subset of `FnmConfig` that can be pinned from a TOML file, shared by
+/// both the per-project and per-user config files.
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct FileConfig {
+    pub node_dist_mirror: Option<String>,
+    pub arch: Option<String>,
+    pub libc: Option<String>,
+    pub log_level: Option<String>,
+    pub base_dir: Option<String>,
+}
+
+/// `fnm.toml`, checked into a project to pin a mirror/arch/libc without
+/// requiring every contributor to export env vars. Found by walking up from
+/// the current directory to the nearest ancestor that has one, the same
+/// way `.node-version`/`.nvmrc` discovery works, so it's still picked up
+/// when fnm is run from a subdirectory of the project.
+pub fn project_config_path() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    cwd.ancestors()
+        .map(|dir| dir.join("fnm.toml"))
+        .find(|candidate| candidate.is_file())
+}
+
+/// `~/.config/fnm/config.toml`, a user-wide default akin to `FNM_*` env vars.
+pub fn user_config_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("fnm")
+        .join("config.toml")
+}
+
+/// Reads and parses a config file at `path`, returning `None` if it doesn't
+/// exist. Malformed files are surfaced as an error rather than ignored.
+pub fn load(path: &Path) -> Result<Option<FileConfig>, FileConfigError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| FileConfigError::new(format!("Can't read {}: {}", path.display(), e)))?;
+    let config: FileConfig = toml::from_str(&contents)
+        .map_err(|e| FileConfigError::new(format!("Can't parse {}: {}", path.display(), e)))?;
+
+    Ok(Some(config))
+}
+
+/// Where a given config value ultimately came from, for `fnm config --show`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Set via an explicit CLI flag.
+    Cli,
+    /// Set via an explicit `FNM_*` env var, with no CLI flag overriding it.
+    Env,
+    ProjectFile,
+    UserFile,
+    Default,
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source_str = match self {
+            ConfigSource::Cli => "cli",
+            ConfigSource::Env => "env",
+            ConfigSource::ProjectFile => "project file",
+            ConfigSource::UserFile => "user file",
+            ConfigSource::Default => "default",
+        };
+        write!(f, "{}", source_str)
+    }
+}
+
+/// Maps the env vars `set_fallback` layers onto the CLI flag that can also
+/// set them, so an explicit flag can be told apart from an explicit env var
+/// for `fnm config --show`'s source attribution.
+const FLAGS_BY_ENV_VAR: &[(&str, &str)] = &[
+    ("FNM_NODE_DIST_MIRROR", "--node-dist-mirror"),
+    ("FNM_ARCH", "--arch"),
+    ("FNM_LIBC", "--libc"),
+    ("FNM_LOGLEVEL", "--log-level"),
+    ("FNM_DIR", "--fnm-dir"),
+];
+
+/// Whether `env_var`'s corresponding CLI flag was passed on the command
+/// line. `apply_env_fallbacks` runs before `FnmConfig::from_args()`, so
+/// `std::env::var_os` alone can't distinguish "CLI flag" from "env var
+/// already set" when both resolve to the same value — this scans the raw
+/// process args instead.
+fn cli_flag_present(env_var: &str) -> bool {
+    let flag = match FLAGS_BY_ENV_VAR.iter().find(|(k, _)| *k == env_var) {
+        Some((_, flag)) => *flag,
+        None => return false,
+    };
+    std::env::args().any(|arg| arg == flag || arg.starts_with(&format!("{}=", flag)))
+}
+
+/// For each of fnm's layered keys, sets the corresponding `FNM_*` env var
+/// from the project file, falling back to the user file, but only when the
+/// env var isn't already set and the matching CLI flag isn't on the command
+/// line — so an explicit env var or CLI flag always wins. Returns the
+/// source that ended up governing each key, for `fnm config --show`.
+///
+/// This must run before `FnmConfig::from_args()` so structopt picks up the
+/// resulting values through its normal env-var handling, giving the
+/// precedence CLI > env > project file > user file > defaults.
+pub fn apply_env_fallbacks(project: &FileConfig, user: &FileConfig) -> Vec<(&'static str, ConfigSource)> {
+    vec![
+        (
+            "FNM_NODE_DIST_MIRROR",
+            set_fallback(
+                "FNM_NODE_DIST_MIRROR",
+                &project.node_dist_mirror,
+                &user.node_dist_mirror,
+            ),
+        ),
+        ("FNM_ARCH", set_fallback("FNM_ARCH", &project.arch, &user.arch)),
+        ("FNM_LIBC", set_fallback("FNM_LIBC", &project.libc, &user.libc)),
+        (
+            "FNM_LOGLEVEL",
+            set_fallback("FNM_LOGLEVEL", &project.log_level, &user.log_level),
+        ),
+        (
+            "FNM_DIR",
+            set_fallback("FNM_DIR", &project.base_dir, &user.base_dir),
+        ),
+    ]
+}
+
+fn set_fallback(
+    env_var: &str,
+    project_value: &Option<String>,
+    user_value: &Option<String>,
+) -> ConfigSource {
+    if cli_flag_present(env_var) {
+        return ConfigSource::Cli;
+    }
+
+    if std::env::var_os(env_var).is_some() {
+        return ConfigSource::Env;
+    }
+
+    if let Some(value) = project_value {
+        std::env::set_var(env_var, value);
+        return ConfigSource::ProjectFile;
+    }
+
+    if let Some(value) = user_value {
+        std::env::set_var(env_var, value);
+        return ConfigSource::UserFile;
+    }
+
+    ConfigSource::Default
+}
+
+/// Loads the project and user config files, treating a missing file as
+/// empty rather than an error.
+pub fn load_layers() -> Result<(FileConfig, FileConfig), FileConfigError> {
+    let project = match project_config_path() {
+        Some(path) => load(&path)?.unwrap_or_default(),
+        None => FileConfig::default(),
+    };
+    let user = load(&user_config_path())?.unwrap_or_default();
+    Ok((project, user))
+}
+
+/// Renders the output of `fnm config --show`: the effective value of each
+/// layered key next to the layer that provided it.
+pub fn format_effective_config(config: &crate::config::FnmConfig, sources: &[(&str, ConfigSource)]) -> String {
+    let source_of = |env_var: &str| {
+        sources
+            .iter()
+            .find(|(key, _)| *key == env_var)
+            .map(|(_, source)| *source)
+            .unwrap_or(ConfigSource::Default)
+    };
+
+    format!(
+        "node_dist_mirror = {} ({})\narch = {} ({})\nlibc = {} ({})\nlog_level = {} ({})\nbase_dir = {} ({})\n",
+        config.node_dist_mirror,
+        source_of("FNM_NODE_DIST_MIRROR"),
+        config.arch,
+        source_of("FNM_ARCH"),
+        config.libc,
+        source_of("FNM_LIBC"),
+        config.log_level(),
+        source_of("FNM_LOGLEVEL"),
+        config.base_dir_with_default().display(),
+        source_of("FNM_DIR"),
+    )
+}
+
+#[derive(Debug)]
+pub struct FileConfigError {
+    details: String,
+}
+
+impl FileConfigError {
+    fn new(msg: impl Into<String>) -> FileConfigError {
+        FileConfigError {
+            details: msg.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for FileConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for FileConfigError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENV_VAR: &str = "FNM_TEST_SET_FALLBACK";
+    static ENV_VAR_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn prefers_project_file_over_user_file() {
+        let _guard = ENV_VAR_LOCK.lock().unwrap();
+        std::env::remove_var(ENV_VAR);
+
+        let source = set_fallback(
+            ENV_VAR,
+            &Some("from-project".to_string()),
+            &Some("from-user".to_string()),
+        );
+
+        assert_eq!(source, ConfigSource::ProjectFile);
+        assert_eq!(std::env::var(ENV_VAR).as_deref(), Ok("from-project"));
+        std::env::remove_var(ENV_VAR);
+    }
+
+    #[test]
+    fn falls_back_to_user_file_when_project_file_unset() {
+        let _guard = ENV_VAR_LOCK.lock().unwrap();
+        std::env::remove_var(ENV_VAR);
+
+        let source = set_fallback(ENV_VAR, &None, &Some("from-user".to_string()));
+
+        assert_eq!(source, ConfigSource::UserFile);
+        assert_eq!(std::env::var(ENV_VAR).as_deref(), Ok("from-user"));
+        std::env::remove_var(ENV_VAR);
+    }
+
+    #[test]
+    fn leaves_an_explicit_env_var_untouched() {
+        let _guard = ENV_VAR_LOCK.lock().unwrap();
+        std::env::set_var(ENV_VAR, "already-set");
+
+        let source = set_fallback(ENV_VAR, &Some("from-project".to_string()), &None);
+
+        assert_eq!(source, ConfigSource::Env);
+        assert_eq!(std::env::var(ENV_VAR).as_deref(), Ok("already-set"));
+        std::env::remove_var(ENV_VAR);
+    }
+
+    #[test]
+    fn defaults_when_nothing_is_set() {
+        let _guard = ENV_VAR_LOCK.lock().unwrap();
+        std::env::remove_var(ENV_VAR);
+
+        let source = set_fallback(ENV_VAR, &None, &None);
+
+        assert_eq!(source, ConfigSource::Default);
+        assert!(std::env::var_os(ENV_VAR).is_none());
+    }
+
+    static CWD_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn project_config_path_finds_fnm_toml_in_an_ancestor_directory() {
+        let _guard = CWD_LOCK.lock().unwrap();
+        let original_cwd = std::env::current_dir().expect("can read the cwd");
+
+        let root = tempfile::tempdir().expect("can create a tempdir");
+        let nested = root.path().join("a/b/c");
+        std::fs::create_dir_all(&nested).expect("can create nested dirs");
+        std::fs::write(root.path().join("fnm.toml"), "").expect("can write fnm.toml");
+
+        std::env::set_current_dir(&nested).expect("can chdir into the nested dir");
+        let found = project_config_path();
+        std::env::set_current_dir(original_cwd).expect("can restore the cwd");
+
+        assert_eq!(found, Some(root.path().join("fnm.toml")));
+    }
+
+    #[test]
+    fn project_config_path_is_none_when_no_ancestor_has_one() {
+        let _guard = CWD_LOCK.lock().unwrap();
+        let original_cwd = std::env::current_dir().expect("can read the cwd");
+
+        let root = tempfile::tempdir().expect("can create a tempdir");
+        std::env::set_current_dir(root.path()).expect("can chdir into the tempdir");
+        let found = project_config_path();
+        std::env::set_current_dir(original_cwd).expect("can restore the cwd");
+
+        assert_eq!(found, None);
+    }
+}
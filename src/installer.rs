@@ -0,0 +1,163 @@
+use std::path::PathBuf;
+
+use crate::arch::{Arch, DownloadPath, InstallDir, LibC};
+use crate::archive_extract::extract_stripping_top_level_dir;
+use crate::build_from_source::{build_from_source, should_fall_back, BuildFromSourceMode, SourceBuildError};
+use crate::config::FnmConfig;
+use crate::local_archive::{self, LocalArchiveError};
+use crate::system_info::platform_name;
+use crate::version::Version;
+
+/// Where a given version is installed to, and the canonical `InstallDir`
+/// implementation every install path (prebuilt or source-built) shares so
+/// they land in the same layout.
+pub struct InstallTarget<'a> {
+    pub config: &'a FnmConfig,
+    pub version: &'a Version,
+}
+
+impl<'a> InstallTarget<'a> {
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(self.install_dir())
+    }
+}
+
+impl<'a> InstallDir for InstallTarget<'a> {
+    fn install_dir(&self) -> String {
+        self.config
+            .installations_dir()
+            .join(self.version.to_string())
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+#[derive(Debug)]
+pub struct InstallError {
+    details: String,
+}
+
+impl InstallError {
+    fn new(msg: impl Into<String>) -> InstallError {
+        InstallError {
+            details: msg.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for InstallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for InstallError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+impl From<SourceBuildError> for InstallError {
+    fn from(e: SourceBuildError) -> Self {
+        InstallError::new(e.to_string())
+    }
+}
+
+impl From<LocalArchiveError> for InstallError {
+    fn from(e: LocalArchiveError) -> Self {
+        InstallError::new(e.to_string())
+    }
+}
+
+/// The prebuilt tarball name for `version`/`arch`/`libc`, matching the
+/// naming convention used by the official and unofficial Node distributions.
+pub fn prebuilt_archive_name(version: &Version, arch: &Arch, libc: &LibC) -> String {
+    format!(
+        "node-{}-{}-{}{}.tar.gz",
+        version,
+        platform_name(),
+        arch.download_path(),
+        libc.download_path()
+    )
+}
+
+fn prebuilt_download_url(config: &FnmConfig, version: &Version, arch: &Arch, libc: &LibC) -> reqwest::Url {
+    config
+        .node_dist_mirror
+        .join(&format!("{}/{}", version, prebuilt_archive_name(version, arch, libc)))
+        .expect("Can't build prebuilt download URL")
+}
+
+/// Downloads and extracts the prebuilt tarball for `version`, returning
+/// `Ok(None)` (rather than an error) when the mirror 404s, so the caller can
+/// decide whether to fall back to a source build.
+fn try_download_prebuilt(
+    config: &FnmConfig,
+    version: &Version,
+    arch: &Arch,
+    libc: &LibC,
+) -> Result<Option<PathBuf>, InstallError> {
+    let url = prebuilt_download_url(config, version, arch, libc);
+    let response = reqwest::blocking::get(url.clone())
+        .map_err(|e| InstallError::new(format!("Can't download {}: {}", url, e)))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(InstallError::new(format!(
+            "Can't download {} (status {})",
+            url,
+            response.status()
+        )));
+    }
+
+    let install_dir = InstallTarget { config, version }.path();
+
+    extract_stripping_top_level_dir(response, &install_dir)
+        .map_err(|e| InstallError::new(format!("Can't extract {}: {}", url, e)))?;
+
+    Ok(Some(install_dir))
+}
+
+/// Installs `version` for `arch`/`libc`. When `FNM_NODE_ARCHIVE` /
+/// `--node-archive` points at a matching local tarball, that's used
+/// directly, skipping the network entirely. Otherwise, tries the prebuilt
+/// tarball from `node_dist_mirror`, falling back to a source build when
+/// the prebuilt tarball 404s and `FNM_BUILD_FROM_SOURCE` is `auto` or
+/// `always` (and skipping straight to a source build when it's `always`),
+/// and failing outright when it's `never`.
+pub fn install(config: &FnmConfig, version: &Version, arch: &Arch, libc: &LibC) -> Result<PathBuf, InstallError> {
+    let install_target = InstallTarget { config, version };
+
+    if let Some(archive_location) = config.node_archive() {
+        if let Some(archive) = local_archive::resolve(&archive_location, version, arch, libc) {
+            let install_dir = install_target.path();
+            std::fs::create_dir_all(&install_dir).map_err(|e| {
+                InstallError::new(format!("Can't create {}: {}", install_dir.display(), e))
+            })?;
+            if let Some(checksum) = local_archive::read_checksum(&archive) {
+                local_archive::verify_checksum(&archive, &checksum)?;
+            }
+            local_archive::extract(&archive, &install_dir)?;
+            return Ok(install_dir);
+        }
+    }
+
+    if config.build_from_source == BuildFromSourceMode::Always {
+        build_from_source(config, version, arch)?;
+        return Ok(install_target.path());
+    }
+
+    match try_download_prebuilt(config, version, arch, libc)? {
+        Some(install_dir) => Ok(install_dir),
+        None if should_fall_back(&config.build_from_source) => {
+            build_from_source(config, version, arch)?;
+            Ok(install_target.path())
+        }
+        None => Err(InstallError::new(format!(
+            "No prebuilt tarball for {} on {}/{} and FNM_BUILD_FROM_SOURCE=never",
+            version, arch, libc
+        ))),
+    }
+}
@@ -0,0 +1,215 @@
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::arch::{Arch, LibC};
+use crate::version::Version;
+
+/// The file:// scheme prefix accepted by `FNM_NODE_ARCHIVE` / `--node-archive`.
+const FILE_SCHEME: &str = "file://";
+
+#[derive(Debug)]
+pub struct LocalArchiveError {
+    details: String,
+}
+
+impl LocalArchiveError {
+    fn new(msg: impl Into<String>) -> LocalArchiveError {
+        LocalArchiveError {
+            details: msg.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for LocalArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for LocalArchiveError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+/// Parses `FNM_NODE_ARCHIVE` / `--node-archive` into a filesystem path,
+/// stripping a `file://` scheme if present.
+pub fn parse_archive_location(raw: &str) -> PathBuf {
+    match raw.strip_prefix(FILE_SCHEME) {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(raw),
+    }
+}
+
+/// The tarball name fnm expects for a given version/arch/libc, matching the
+/// naming convention used by the official and unofficial Node distributions.
+/// Delegates to `installer::prebuilt_archive_name`, the single source of
+/// truth for this format, so a local archive is expected to be named
+/// exactly like the tarball a normal install would have downloaded.
+pub fn expected_archive_name(version: &Version, arch: &Arch, libc: &LibC) -> String {
+    crate::installer::prebuilt_archive_name(version, arch, libc)
+}
+
+/// Resolves `location` (either a single tarball or a directory of cached
+/// tarballs) to an archive. A single tarball is returned as-is; its name is
+/// not checked against `version`/`arch`/`libc`. For a directory, the
+/// tarball named `expected_archive_name(version, arch, libc)` is returned,
+/// if present.
+pub fn resolve(
+    location: &Path,
+    version: &Version,
+    arch: &Arch,
+    libc: &LibC,
+) -> Option<PathBuf> {
+    if location.is_file() {
+        return Some(location.to_path_buf());
+    }
+
+    let candidate = location.join(expected_archive_name(version, arch, libc));
+    if candidate.is_file() {
+        return Some(candidate);
+    }
+
+    None
+}
+
+/// Verifies `archive`'s SHA-256 checksum against `expected_sha256` (e.g. as
+/// read by `read_checksum` from a sibling `<archive>.sha256` file).
+pub fn verify_checksum(archive: &Path, expected_sha256: &str) -> Result<(), LocalArchiveError> {
+    let bytes = std::fs::read(archive)
+        .map_err(|e| LocalArchiveError::new(format!("Can't read {}: {}", archive.display(), e)))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    let actual = format!("{:x}", hasher.finalize());
+
+    if !actual.eq_ignore_ascii_case(expected_sha256) {
+        return Err(LocalArchiveError::new(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            archive.display(),
+            expected_sha256,
+            actual
+        )));
+    }
+
+    Ok(())
+}
+
+/// Extracts a local `.tar.gz` archive directly into `install_dir`, skipping
+/// the network entirely. Like a downloaded Node tarball, a local archive
+/// wraps its contents in a single top-level `node-{version}-{platform}-
+/// {arch}/` directory, which is stripped so `install_dir` ends up holding
+/// the tree directly (`install_dir/bin/node`, not
+/// `install_dir/node-.../bin/node`).
+pub fn extract(archive: &Path, install_dir: &Path) -> Result<(), LocalArchiveError> {
+    let file = std::fs::File::open(archive)
+        .map_err(|e| LocalArchiveError::new(format!("Can't open {}: {}", archive.display(), e)))?;
+    crate::archive_extract::extract_stripping_top_level_dir(file, install_dir)
+        .map_err(|e| LocalArchiveError::new(format!("Can't extract archive: {}", e)))
+}
+
+/// Reads the expected SHA-256 checksum for `archive` from a sibling
+/// `<archive>.sha256` file, if one exists.
+pub fn read_checksum(archive: &Path) -> Option<String> {
+    let mut checksum_path = archive.as_os_str().to_os_string();
+    checksum_path.push(".sha256");
+    let contents = std::fs::read_to_string(PathBuf::from(checksum_path)).ok()?;
+    contents.split_whitespace().next().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system_info::platform_name;
+
+    #[test]
+    fn expected_archive_name_matches_the_installer_s_naming() {
+        let version: Version = "v18.16.0".parse().expect("valid version");
+        let name = expected_archive_name(&version, &Arch::X64, &LibC::Glibc);
+        assert_eq!(
+            name,
+            crate::installer::prebuilt_archive_name(&version, &Arch::X64, &LibC::Glibc)
+        );
+    }
+
+    #[test]
+    fn expected_archive_name_includes_the_libc_suffix_for_musl() {
+        let version: Version = "v18.16.0".parse().expect("valid version");
+        let name = expected_archive_name(&version, &Arch::X64, &LibC::Musl);
+        assert_eq!(
+            name,
+            format!("node-v18.16.0-{}-x64-musl.tar.gz", platform_name())
+        );
+    }
+
+    #[test]
+    fn verify_checksum_accepts_a_matching_sha256() {
+        let dir = tempfile::tempdir().expect("can create a tempdir");
+        let archive = dir.path().join("node.tar.gz");
+        std::fs::write(&archive, "fake tarball contents").expect("can write the fixture archive");
+
+        verify_checksum(
+            &archive,
+            "8761ded538856288531a4eee97ae0b847c095d902b60d3a2c723fc3d1d687416",
+        )
+        .expect("checksum should match");
+    }
+
+    #[test]
+    fn verify_checksum_rejects_a_mismatching_sha256() {
+        let dir = tempfile::tempdir().expect("can create a tempdir");
+        let archive = dir.path().join("node.tar.gz");
+        std::fs::write(&archive, "fake tarball contents").expect("can write the fixture archive");
+
+        let result = verify_checksum(&archive, &"0".repeat(64));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn read_checksum_reads_the_first_whitespace_separated_token() {
+        let dir = tempfile::tempdir().expect("can create a tempdir");
+        let archive = dir.path().join("node.tar.gz");
+        std::fs::write(format!("{}.sha256", archive.display()), "abc123  node.tar.gz\n")
+            .expect("can write the fixture checksum file");
+
+        assert_eq!(read_checksum(&archive), Some("abc123".to_string()));
+    }
+
+    #[test]
+    fn read_checksum_is_none_when_the_sidecar_file_is_missing() {
+        let dir = tempfile::tempdir().expect("can create a tempdir");
+        let archive = dir.path().join("node.tar.gz");
+
+        assert_eq!(read_checksum(&archive), None);
+    }
+
+    #[test]
+    fn extract_strips_the_top_level_directory() {
+        let dir = tempfile::tempdir().expect("can create a tempdir");
+        let archive_path = dir.path().join("node-v18.16.0-linux-x64.tar.gz");
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let contents = b"#!/usr/bin/env node\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "node-v18.16.0-linux-x64/bin/node", &contents[..])
+            .expect("can append a tar entry");
+        let tar_bytes = builder.into_inner().expect("can finish the tar archive");
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &tar_bytes).expect("can gzip the tar archive");
+        std::fs::write(&archive_path, encoder.finish().expect("can finish gzip encoding"))
+            .expect("can write the fixture archive");
+
+        let install_dir = dir.path().join("install");
+        std::fs::create_dir_all(&install_dir).expect("can create the install dir");
+
+        extract(&archive_path, &install_dir).expect("should extract");
+
+        assert!(install_dir.join("bin/node").is_file());
+        assert!(!install_dir.join("node-v18.16.0-linux-x64").exists());
+    }
+}